@@ -0,0 +1,259 @@
+use bevy::{
+    math::*,
+    prelude::*
+};
+
+use crate::surface::Surface;
+
+#[derive(Clone, Copy)]
+struct Aabb {
+    min: Vec3,
+    max: Vec3
+}
+
+impl Aabb {
+    fn empty() -> Aabb {
+        Aabb {
+            min: Vec3::splat(f32::INFINITY),
+            max: Vec3::splat(f32::NEG_INFINITY)
+        }
+    }
+
+    fn grow(&mut self, p: Vec3) {
+        self.min = self.min.min(p);
+        self.max = self.max.max(p);
+    }
+
+    fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max)
+        }
+    }
+
+    fn centroid(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    // Slab test; returns the entry distance along the ray if it hits.
+    fn intersect(&self, origin: Vec3, inv_dir: Vec3) -> Option<f32> {
+        let t0 = (self.min - origin) * inv_dir;
+        let t1 = (self.max - origin) * inv_dir;
+        let tmin = t0.min(t1);
+        let tmax = t0.max(t1);
+        let enter = tmin.max_element();
+        let exit = tmax.min_element();
+        if exit >= enter.max(0.0) {
+            Some(enter)
+        } else {
+            None
+        }
+    }
+}
+
+const LEAF_SIZE: usize = 4;
+
+enum BvhNode {
+    Leaf { aabb: Aabb, triangles: Vec<u32> },
+    Inner { aabb: Aabb, left: Box<BvhNode>, right: Box<BvhNode> }
+}
+
+impl BvhNode {
+    fn aabb(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { aabb, .. } => aabb,
+            BvhNode::Inner { aabb, .. } => aabb
+        }
+    }
+}
+
+pub struct Bvh {
+    root: BvhNode
+}
+
+fn triangle_aabb(positions: &[Vec3], t: &[u32; 3]) -> Aabb {
+    let mut aabb = Aabb::empty();
+    aabb.grow(positions[t[0] as usize]);
+    aabb.grow(positions[t[1] as usize]);
+    aabb.grow(positions[t[2] as usize]);
+    aabb
+}
+
+fn build_node(positions: &[Vec3], triangles: &[(u32, Aabb)]) -> BvhNode {
+    let bounds = triangles
+        .iter()
+        .map(|(_, aabb)| Aabb {
+            min: aabb.min,
+            max: aabb.max
+        })
+        .fold(Aabb::empty(), |acc, aabb| acc.union(&aabb));
+
+    if triangles.len() <= LEAF_SIZE {
+        return BvhNode::Leaf {
+            aabb: bounds,
+            triangles: triangles.iter().map(|(i, _)| *i).collect()
+        };
+    }
+
+    let centroid_bounds = triangles
+        .iter()
+        .fold(Aabb::empty(), |mut acc, (_, aabb)| {
+            acc.grow(aabb.centroid());
+            acc
+        });
+    let extent = centroid_bounds.max - centroid_bounds.min;
+    let axis = if extent.x() >= extent.y() && extent.x() >= extent.z() {
+        0
+    } else if extent.y() >= extent.z() {
+        1
+    } else {
+        2
+    };
+
+    let mut sorted = triangles.to_vec();
+    sorted.sort_by(|(_, a), (_, b)| {
+        a.centroid()[axis].partial_cmp(&b.centroid()[axis]).unwrap()
+    });
+
+    let mid = sorted.len() / 2;
+    let (left, right) = sorted.split_at(mid);
+
+    BvhNode::Inner {
+        aabb: bounds,
+        left: Box::new(build_node(positions, left)),
+        right: Box::new(build_node(positions, right))
+    }
+}
+
+pub fn build_bvh(surface: &Surface) -> Bvh {
+    let positions = surface.positions();
+    let triangles: Vec<(u32, Aabb)> = surface
+        .triangles()
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (i as u32, triangle_aabb(positions, t)))
+        .collect();
+
+    Bvh {
+        root: build_node(positions, &triangles)
+    }
+}
+
+// Moller-Trumbore ray/triangle intersection. Returns (t, u, v) where
+// `u`/`v` are barycentric weights of vertices b and c (1 - u - v is a's).
+fn intersect_triangle(origin: Vec3, dir: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<(f32, f32, f32)> {
+    const EPS: f32 = 1e-6;
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let pvec = dir.cross(edge2);
+    let det = edge1.dot(pvec);
+    if det.abs() < EPS {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let tvec = origin - a;
+    let u = tvec.dot(pvec) * inv_det;
+    if u < 0.0 || u > 1.0 {
+        return None;
+    }
+    let qvec = tvec.cross(edge1);
+    let v = dir.dot(qvec) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(qvec) * inv_det;
+    if t < EPS {
+        return None;
+    }
+    Some((t, u, v))
+}
+
+struct Hit {
+    t: f32,
+    param: Vec2
+}
+
+fn traverse(
+    node: &BvhNode,
+    surface: &Surface,
+    origin: Vec3,
+    dir: Vec3,
+    inv_dir: Vec3,
+    best: &mut Option<Hit>
+) {
+    let node_t = match node.aabb().intersect(origin, inv_dir) {
+        Some(t) => t,
+        None => return
+    };
+    if let Some(hit) = best {
+        if node_t > hit.t {
+            return;
+        }
+    }
+
+    match node {
+        BvhNode::Leaf { triangles, .. } => {
+            let positions = surface.positions();
+            let params = surface.params();
+            for &i in triangles {
+                let t = surface.triangles()[i as usize];
+                let a = positions[t[0] as usize];
+                let b = positions[t[1] as usize];
+                let c = positions[t[2] as usize];
+                if let Some((hit_t, u, v)) = intersect_triangle(origin, dir, a, b, c) {
+                    if best.as_ref().map_or(true, |h| hit_t < h.t) {
+                        let pa = params[t[0] as usize];
+                        let pb = params[t[1] as usize];
+                        let pc = params[t[2] as usize];
+                        let param = pa * (1.0 - u - v) + pb * u + pc * v;
+                        *best = Some(Hit { t: hit_t, param });
+                    }
+                }
+            }
+        }
+        BvhNode::Inner { left, right, .. } => {
+            let left_t = left.aabb().intersect(origin, inv_dir);
+            let right_t = right.aabb().intersect(origin, inv_dir);
+            let (first, second) = match (left_t, right_t) {
+                (Some(lt), Some(rt)) if rt < lt => (right, left),
+                _ => (left, right)
+            };
+            traverse(first, surface, origin, dir, inv_dir, best);
+            traverse(second, surface, origin, dir, inv_dir, best);
+        }
+    }
+}
+
+// Cast a ray, given in `surface`'s local coordinates (the frame its
+// positions and BVH are in), against the BVH and return the hit's
+// parameter-domain point, reconstructed from the barycentric weights of
+// the nearest triangle.
+pub fn raycast(bvh: &Bvh, surface: &Surface, origin: Vec3, dir: Vec3) -> Option<Vec2> {
+    let inv_dir = Vec3::new(1.0 / dir.x(), 1.0 / dir.y(), 1.0 / dir.z());
+    let mut best = None;
+    traverse(&bvh.root, surface, origin, dir, inv_dir, &mut best);
+    best.map(|hit| hit.param)
+}
+
+// Unproject a cursor position (in window pixels, origin bottom-left) into a
+// world-space ray through the camera.
+pub fn cursor_ray(
+    camera: &Camera,
+    camera_transform: &Transform,
+    window_size: Vec2,
+    cursor_pos: Vec2
+) -> (Vec3, Vec3) {
+    let ndc = Vec2::new(
+        2.0 * cursor_pos.x() / window_size.x() - 1.0,
+        2.0 * cursor_pos.y() / window_size.y() - 1.0
+    );
+    let view_proj = camera.projection_matrix * camera_transform.value.inverse();
+    let inv_view_proj = view_proj.inverse();
+
+    let near = inv_view_proj * Vec4::new(ndc.x(), ndc.y(), -1.0, 1.0);
+    let far = inv_view_proj * Vec4::new(ndc.x(), ndc.y(), 1.0, 1.0);
+    let near = Vec3::new(near.x(), near.y(), near.z()) / near.w();
+    let far = Vec3::new(far.x(), far.y(), far.z()) / far.w();
+
+    (near, (far - near).normalize())
+}
@@ -0,0 +1,115 @@
+use bevy::{
+    input::{keyboard::*, mouse::*},
+    prelude::*
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Action {
+    AddZero,
+    AddPole,
+    Quit,
+    OrbitCamera,
+    ClearMarkers
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Input {
+    Key(KeyCode),
+    Mouse(MouseButton)
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ActionState {
+    Inactive,
+    JustActivated,
+    Activated,
+    JustDeactivated
+}
+
+// Maps logical actions to physical inputs and tracks each action's
+// press/release state, so systems can query `bindings.just_activated(...)`
+// instead of matching raw key/button events directly. Remapping a control
+// is then a one-line change to the default bindings below.
+pub struct InputBindings {
+    bindings: HashMap<Action, Vec<Input>>,
+    held: HashSet<Input>,
+    states: HashMap<Action, ActionState>,
+    keyboard_reader: EventReader<KeyboardInput>,
+    mouse_reader: EventReader<MouseButtonInput>
+}
+
+impl Default for InputBindings {
+    fn default() -> InputBindings {
+        let mut bindings = HashMap::new();
+        bindings.insert(Action::AddZero, vec![Input::Mouse(MouseButton::Left)]);
+        bindings.insert(Action::AddPole, vec![Input::Mouse(MouseButton::Right)]);
+        bindings.insert(Action::Quit, vec![Input::Key(KeyCode::Escape)]);
+        bindings.insert(Action::OrbitCamera, vec![Input::Mouse(MouseButton::Middle)]);
+        bindings.insert(Action::ClearMarkers, vec![Input::Key(KeyCode::C)]);
+        InputBindings {
+            bindings,
+            held: HashSet::new(),
+            states: HashMap::new(),
+            keyboard_reader: EventReader::default(),
+            mouse_reader: EventReader::default()
+        }
+    }
+}
+
+impl InputBindings {
+    pub fn just_activated(&self, action: Action) -> bool {
+        self.states.get(&action) == Some(&ActionState::JustActivated)
+    }
+
+    pub fn activated(&self, action: Action) -> bool {
+        match self.states.get(&action) {
+            Some(ActionState::JustActivated) | Some(ActionState::Activated) => true,
+            _ => false
+        }
+    }
+
+    pub fn just_deactivated(&self, action: Action) -> bool {
+        self.states.get(&action) == Some(&ActionState::JustDeactivated)
+    }
+
+    fn update_action_states(&mut self) {
+        let actions: Vec<Action> = self.bindings.keys().copied().collect();
+        for action in actions {
+            let raw_active = self.bindings[&action].iter().any(|i| self.held.contains(i));
+            let was_active = self.activated(action);
+            let state = match (was_active, raw_active) {
+                (false, true) => ActionState::JustActivated,
+                (true, true) => ActionState::Activated,
+                (true, false) => ActionState::JustDeactivated,
+                (false, false) => ActionState::Inactive
+            };
+            self.states.insert(action, state);
+        }
+    }
+}
+
+pub fn input_binding_system(
+    mut bindings: ResMut<InputBindings>,
+    keyboard_events: Res<Events<KeyboardInput>>,
+    mouse_events: Res<Events<MouseButtonInput>>
+) {
+    for event in bindings.keyboard_reader.iter(&keyboard_events) {
+        if let Some(key_code) = event.key_code {
+            let input = Input::Key(key_code);
+            match event.state {
+                ElementState::Pressed => { bindings.held.insert(input); }
+                ElementState::Released => { bindings.held.remove(&input); }
+            }
+        }
+    }
+    for event in bindings.mouse_reader.iter(&mouse_events) {
+        let input = Input::Mouse(event.button);
+        match event.state {
+            ElementState::Pressed => { bindings.held.insert(input); }
+            ElementState::Released => { bindings.held.remove(&input); }
+        }
+    }
+
+    bindings.update_action_states();
+}
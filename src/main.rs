@@ -12,9 +12,16 @@ use bevy::{
     }
 };
 
+mod bvh;
+mod input;
+mod markers;
 mod sphere;
 mod surface;
+mod terrain;
 
+use bvh::Bvh;
+use input::{input_binding_system, Action, InputBindings};
+use markers::{marker_system, MarkerAssets};
 use surface::*;
 
 fn main() {
@@ -27,45 +34,50 @@ fn main() {
         .add_default_plugins()
         .add_startup_system(setup.system())
         .init_resource::<CursorState>()
-        .init_resource::<MouseClickState>()
         .init_resource::<MouseMotionState>()
-        .init_resource::<KeyboardState>()
+        .init_resource::<InputBindings>()
+        .add_system(input_binding_system.system())
         .add_system(mouse_motion_system.system())
         .add_system(keyboard_system.system())
+        .add_system(clear_markers_system.system())
         .add_system(cursor_system.system())
         .add_system(mouse_click_system.system())
         .add_system(mesh_system.system())
+        .add_system(marker_system.system())
         .run();
 }
 
-#[derive(Default)]
-struct MouseClickState {
-    reader: EventReader<MouseButtonInput>
-}
-
 fn mouse_click_system(
-    mut state: ResMut<MouseClickState>,
-    events: Res<Events<MouseButtonInput>>,
+    bindings: Res<InputBindings>,
     cursor: Res<CursorState>,
-    mut query: Query<&mut Mero>
+    windows: Res<Windows>,
+    mut camera_query: Query<(&Camera, &Transform)>,
+    mut query: Query<(&Surface, &Bvh, &Translation, &mut Mero)>
 ) {
-    let real_pos = cursor.position / 500. - Vec2::new(1.0, 1.0);
-    for event in state.reader.iter(&events) {
-        for mut mero in &mut query.iter() {
-            if event.state == ElementState::Released {
-                match event.button {
-                    MouseButton::Left => {
-                        println!("Adding a zero at position: {}", real_pos);
-                        let z = to_complex(real_pos);
-                        mero.zeros.push(z);
-                    }
-                    MouseButton::Right => {
-                        println!("Adding a pole at position: {}", real_pos);
-                        let z = to_complex(real_pos);
-                        mero.poles.push(z);
-                    }
-                    _ => {}
-                }
+    let add_zero = bindings.just_activated(Action::AddZero);
+    let add_pole = bindings.just_activated(Action::AddPole);
+    if !add_zero && !add_pole {
+        return;
+    }
+
+    let window = windows.get_primary().unwrap();
+    let window_size = Vec2::new(window.width as f32, window.height as f32);
+
+    for (camera, camera_transform) in &mut camera_query.iter() {
+        let (origin, dir) = bvh::cursor_ray(camera, camera_transform, window_size, cursor.position);
+        for (surface, surf_bvh, translation, mut mero) in &mut query.iter() {
+            let local_origin = origin - translation.0;
+            let hit = match bvh::raycast(surf_bvh, surface, local_origin, dir) {
+                Some(hit) => hit,
+                None => continue
+            };
+            if add_zero {
+                println!("Adding a zero at position: {}", hit);
+                mero.zeros.push(to_complex(hit));
+            }
+            if add_pole {
+                println!("Adding a pole at position: {}", hit);
+                mero.poles.push(to_complex(hit));
             }
         }
     }
@@ -94,12 +106,16 @@ struct MouseMotionState {
 fn mouse_motion_system(
     mut state: ResMut<MouseMotionState>,
     events: Res<Events<MouseMotion>>,
+    bindings: Res<InputBindings>,
     mut query: Query<(&Camera, &mut Translation, &mut Transform)>
 ) {
     let mut delta = Vec2::zero();
     for event in state.reader.iter(&events) {
         delta += event.delta;
     }
+    if !bindings.activated(Action::OrbitCamera) {
+        return;
+    }
     delta.set_x(-delta.x());
     for (_camera, mut pos, mut trans) in &mut query.iter() {
         let q = delta.extend(0.0);
@@ -112,34 +128,45 @@ fn mouse_motion_system(
     }
 }
 
-#[derive(Default)]
-struct KeyboardState {
-    reader: EventReader<KeyboardInput>
+fn keyboard_system(
+    bindings: Res<InputBindings>,
+    mut exit_events: ResMut<Events<AppExit>>
+) {
+    if bindings.just_activated(Action::Quit) {
+        exit_events.send(AppExit)
+    }
 }
 
-fn keyboard_system(
-    mut state: ResMut<KeyboardState>,
-    mut exit_events: ResMut<Events<AppExit>>,
-    keyboard_events: Res<Events<KeyboardInput>>
+fn clear_markers_system(
+    bindings: Res<InputBindings>,
+    mut query: Query<&mut Mero>
 ) {
-    for event in state.reader.iter(&keyboard_events) {
-        if event.state == ElementState::Pressed && event.key_code == Some(KeyCode::Escape) {
-            exit_events.send(AppExit)
-        }
+    if !bindings.just_activated(Action::ClearMarkers) {
+        return;
+    }
+    for mut mero in &mut query.iter() {
+        mero.zeros.clear();
+        mero.poles.clear();
     }
 }
 
 fn mesh_system(
     mut meshes: ResMut<Assets<Mesh>>,
-    mut query: Query<(Changed<Mero>, &Surface, &Handle<Mesh>)>
+    mut query: Query<(Changed<Mero>, &Surface, &Handle<Mesh>, &MeshColoring)>
 ) {
-    for (mero, surface, handle) in &mut query.iter() {
+    for (mero, surface, handle, coloring) in &mut query.iter() {
         if let Some(mesh) = meshes.get_mut(handle) {
             println!("Updating mesh");
-            let pol = |z| poly(&mero, z);
-            // TODO: do not recreate the mesh, only update the uvs
-            let solid_mesh = surface_to_solid(&surface, pol);
-            *mesh = solid_mesh;
+            match coloring {
+                MeshColoring::Textured => {
+                    let pol = |z| poly(&mero, z);
+                    update_uvs(mesh, &surface, pol);
+                }
+                MeshColoring::DomainColored => {
+                    let eval = |z| eval_mero(&mero, z);
+                    update_colors(mesh, &surface, eval);
+                }
+            }
         }
     }
 }
@@ -151,6 +178,18 @@ fn setup(
     mut textures: ResMut<Assets<Texture>>,
     mut materials: ResMut<Assets<StandardMaterial>>
 ) {
+    let marker_bound = Vec2::new(0.3, 0.3);
+    let marker_mesh = meshes.add(surface_to_solid(
+        &parametric_surface(-marker_bound, marker_bound, [8, 8], |z| sphere::north_chart(z) * 0.05),
+        identity
+    ));
+    let marker_assets = MarkerAssets {
+        mesh: marker_mesh,
+        zero_material: materials.add(Color::rgb(0.1, 0.9, 0.2).into()),
+        pole_material: materials.add(Color::rgb(0.9, 0.1, 0.1).into())
+    };
+    commands.insert_resource(marker_assets);
+
     let texture_handle = asset_server
         .load_sync(&mut textures, "assets/periodic.png")
         .unwrap();
@@ -179,14 +218,85 @@ fn setup(
         ..Default::default()
     };
 
+    let terrain_bound = Vec2::new(4., 4.);
+    let terrain_surface = parametric_surface(-terrain_bound, terrain_bound, [200, 200], terrain::terrain(0));
+
+    let solid_mesh = meshes.add(surface_to_solid(&terrain_surface, pol));
+    let solid_material = materials.add(texture_handle.into());
+    let solid_terrain = PbrComponents {
+        mesh: solid_mesh,
+        material: solid_material,
+        translation: Translation::new(10.0, 0.5, 0.0),
+        ..Default::default()
+    };
+
+    let domain_colored_surface = parametric_surface(-bound, bound, [200, 200], sphere::north_chart);
+    let domain_colored_eval = |z| eval_mero(&Mero::new(), z);
+    let domain_colored_mesh = meshes.add(surface_to_domain_colored(&domain_colored_surface, domain_colored_eval));
+    let domain_colored_material = materials.add(Color::rgb(1.0, 1.0, 1.0).into());
+    let solid_domain_colored = PbrComponents {
+        mesh: domain_colored_mesh,
+        material: domain_colored_material,
+        translation: Translation::new(16.0, 0.5, 0.0),
+        ..Default::default()
+    };
+
+    let tube_profile: Vec<Vec2> = (0..=12)
+        .map(|i| {
+            let theta = 2.0 * std::f32::consts::PI * i as f32 / 12.0;
+            Vec2::new(0.3 * theta.cos(), 0.3 * theta.sin())
+        })
+        .collect();
+    let tube_path: Vec<Vec3> = (0..=64)
+        .map(|i| {
+            let t = i as f32 / 64.0;
+            Vec3::new(4.0 * t, 0.5 * f32::sin(2.0 * std::f32::consts::PI * t), 0.0)
+        })
+        .collect();
+    let tube_surface = extrusion(&tube_profile, &tube_path);
+
+    let solid_mesh = meshes.add(surface_to_solid(&tube_surface, pol));
+    let solid_material = materials.add(texture_handle.into());
+    let solid_tube = PbrComponents {
+        mesh: solid_mesh,
+        material: solid_material,
+        translation: Translation::new(-6.0, 0.5, 0.0),
+        ..Default::default()
+    };
+
+    let plane_bvh = bvh::build_bvh(&plane_surface);
+    let sphere_bvh = bvh::build_bvh(&sphere_surface);
+    let terrain_bvh = bvh::build_bvh(&terrain_surface);
+    let domain_colored_bvh = bvh::build_bvh(&domain_colored_surface);
+    let tube_bvh = bvh::build_bvh(&tube_surface);
+
     let camera_pos = Vec3::new(0., 0., 10.);
 
     commands
         .spawn(solid_plane)
         .with(plane_surface)
+        .with(plane_bvh)
+        .with(MeshColoring::Textured)
         .with(Mero::new())
         .spawn(solid_sphere)
         .with(sphere_surface)
+        .with(sphere_bvh)
+        .with(MeshColoring::Textured)
+        .with(Mero::new())
+        .spawn(solid_terrain)
+        .with(terrain_surface)
+        .with(terrain_bvh)
+        .with(MeshColoring::Textured)
+        .with(Mero::new())
+        .spawn(solid_domain_colored)
+        .with(domain_colored_surface)
+        .with(domain_colored_bvh)
+        .with(MeshColoring::DomainColored)
+        .with(Mero::new())
+        .spawn(solid_tube)
+        .with(tube_surface)
+        .with(tube_bvh)
+        .with(MeshColoring::Textured)
         .with(Mero::new())
         .spawn(Camera3dComponents {
             translation: Translation(camera_pos),
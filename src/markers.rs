@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use crate::surface::{from_complex, Mero, Surface};
+
+// A small shared marker mesh (a cap of a sphere, taken from a patch of
+// `sphere::north_chart`) and the two marker materials, built once in
+// `setup` and reused for every zero/pole marker.
+pub struct MarkerAssets {
+    pub mesh: Handle<Mesh>,
+    pub zero_material: Handle<StandardMaterial>,
+    pub pole_material: Handle<StandardMaterial>
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+enum MarkerKind {
+    Zero,
+    Pole
+}
+
+struct Marker {
+    owner: Entity,
+    kind: MarkerKind,
+    index: usize
+}
+
+// Watches `Mero::zeros`/`poles` on each surface and keeps a matching set of
+// child marker entities in sync, spawning/despawning only the entries that
+// changed rather than rebuilding all markers every edit.
+pub fn marker_system(
+    mut commands: Commands,
+    marker_assets: Res<MarkerAssets>,
+    mut marker_query: Query<(Entity, &Marker)>,
+    mut surface_query: Query<(Entity, Changed<Mero>, &Surface)>
+) {
+    for (owner, mero, surface) in &mut surface_query.iter() {
+        let mut existing = HashSet::new();
+        for (marker_entity, marker) in &mut marker_query.iter() {
+            if marker.owner != owner {
+                continue;
+            }
+            let key = (marker.kind, marker.index);
+            if (marker.kind == MarkerKind::Zero && marker.index < mero.zeros.len())
+                || (marker.kind == MarkerKind::Pole && marker.index < mero.poles.len())
+            {
+                existing.insert(key);
+            } else {
+                commands.despawn(marker_entity);
+            }
+        }
+
+        for (index, zero) in mero.zeros.iter().enumerate() {
+            if !existing.contains(&(MarkerKind::Zero, index)) {
+                spawn_marker(&mut commands, &marker_assets, owner, MarkerKind::Zero, index, surface.nearest_position(from_complex(*zero)));
+            }
+        }
+        for (index, pole) in mero.poles.iter().enumerate() {
+            if !existing.contains(&(MarkerKind::Pole, index)) {
+                spawn_marker(&mut commands, &marker_assets, owner, MarkerKind::Pole, index, surface.nearest_position(from_complex(*pole)));
+            }
+        }
+    }
+}
+
+fn spawn_marker(
+    commands: &mut Commands,
+    marker_assets: &MarkerAssets,
+    owner: Entity,
+    kind: MarkerKind,
+    index: usize,
+    position: Vec3
+) {
+    let material = match kind {
+        MarkerKind::Zero => marker_assets.zero_material.clone(),
+        MarkerKind::Pole => marker_assets.pole_material.clone()
+    };
+    commands
+        .spawn(PbrComponents {
+            mesh: marker_assets.mesh.clone(),
+            material,
+            translation: Translation(position),
+            ..Default::default()
+        })
+        .with(Marker { owner, kind, index })
+        .with(Parent(owner));
+}
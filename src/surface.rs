@@ -15,9 +15,44 @@ pub struct Surface {
     count: [u32; 2],
     positions: Vec<Vec3>,
     normals: Vec<Vec3>,
+    // Parameter-domain point each vertex was evaluated at, in the same
+    // order as `positions`/`normals`. Lets picking map a hit back to the
+    // domain point without re-deriving it from the grid.
+    params: Vec<Vec2>,
     triangles: Vec<[u32; 3]>
 }
 
+impl Surface {
+    pub(crate) fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    pub(crate) fn params(&self) -> &[Vec2] {
+        &self.params
+    }
+
+    pub(crate) fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    // Nearest-neighbor lookup of the 3D position a parameter-domain point
+    // maps to, by scanning the precomputed `params`/`positions` pair. Used
+    // to place markers at arbitrary (off-grid) zero/pole locations without
+    // needing to keep the original evaluator function around.
+    pub(crate) fn nearest_position(&self, z: Vec2) -> Vec3 {
+        let mut best_index = 0;
+        let mut best_dist = f32::INFINITY;
+        for (i, p) in self.params.iter().enumerate() {
+            let dist = (*p - z).length_squared();
+            if dist < best_dist {
+                best_dist = dist;
+                best_index = i;
+            }
+        }
+        self.positions[best_index]
+    }
+}
+
 fn triangle_normal(a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
     (b - a).cross(c - a)
 }
@@ -52,6 +87,7 @@ where
 {
     let mut positions = Vec::new();
     let mut normals = Vec::new();
+    let mut params = Vec::new();
     let mut triangles = Vec::new();
 
     let diff = end - start;
@@ -64,6 +100,7 @@ where
             let x = start[0] + i as f32 * delta_x;
             let y = start[1] + j as f32 * delta_y;
             let z = Vec2::new(x, y);
+            params.push(z);
             let x_eps = Vec2::new(eps, 0.0);
             let y_eps = Vec2::new(0.0, eps);
             let o = f(z);
@@ -98,6 +135,105 @@ where
         count,
         positions,
         normals,
+        params,
+        triangles
+    }
+}
+
+// Rotate `v` by the minimal rotation that takes `from` to `to` (Rodrigues'
+// rotation formula). Used to parallel-transport a sweep frame along a path
+// without accumulating twist.
+fn rotate_towards(v: Vec3, from: Vec3, to: Vec3) -> Vec3 {
+    let axis = from.cross(to);
+    let axis_len = axis.length();
+    if axis_len < 1e-6 {
+        return v;
+    }
+    let axis = axis / axis_len;
+    let cos_theta = from.dot(to).max(-1.0).min(1.0);
+    let theta = cos_theta.acos();
+    v * theta.cos() + axis.cross(v) * theta.sin() + axis * axis.dot(v) * (1.0 - theta.cos())
+}
+
+fn accumulate_normals(positions: &[Vec3], triangles: &[[u32; 3]]) -> Vec<Vec3> {
+    let mut normals = vec![Vec3::zero(); positions.len()];
+    for t in triangles {
+        let a = positions[t[0] as usize];
+        let b = positions[t[1] as usize];
+        let c = positions[t[2] as usize];
+        let n = triangle_normal(a, b, c);
+        normals[t[0] as usize] += n;
+        normals[t[1] as usize] += n;
+        normals[t[2] as usize] += n;
+    }
+    normals.iter().map(|n| n.normalize()).collect()
+}
+
+// Sweep a 2D cross-section `profile` along a 3D `path` to build a tube,
+// ribbon, or generalized cylinder. At each path point the frame's "up" axis
+// is parallel-transported from the previous ring to avoid twist.
+pub fn extrusion(profile: &[Vec2], path: &[Vec3]) -> Surface {
+    let ring_count = path.len() as u32 - 1;
+    let profile_count = profile.len() as u32 - 1;
+
+    let mut tangents = Vec::with_capacity(path.len());
+    for i in 0..path.len() {
+        let tangent = if i == 0 {
+            (path[i + 1] - path[i]).normalize()
+        } else if i == path.len() - 1 {
+            (path[i] - path[i - 1]).normalize()
+        } else {
+            let incoming = (path[i] - path[i - 1]).normalize();
+            let outgoing = (path[i + 1] - path[i]).normalize();
+            (incoming + outgoing).normalize()
+        };
+        tangents.push(tangent);
+    }
+
+    let reference = if tangents[0].dot(Vec3::new(0.0, 1.0, 0.0)).abs() > 0.99 {
+        Vec3::new(1.0, 0.0, 0.0)
+    } else {
+        Vec3::new(0.0, 1.0, 0.0)
+    };
+    let mut right = tangents[0].cross(reference).normalize();
+    let mut up = tangents[0].cross(right).normalize();
+
+    let mut positions = Vec::with_capacity(path.len() * profile.len());
+    let mut params = Vec::with_capacity(path.len() * profile.len());
+    for i in 0..path.len() {
+        if i > 0 {
+            let prev_tangent = tangents[i - 1];
+            let tangent = tangents[i];
+            right = rotate_towards(right, prev_tangent, tangent);
+            up = rotate_towards(up, prev_tangent, tangent);
+        }
+        for (j, p) in profile.iter().enumerate() {
+            positions.push(path[i] + p[0] * right + p[1] * up);
+            params.push(Vec2::new(i as f32, j as f32));
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for i in 0..ring_count {
+        for j in 0..profile_count {
+            let bottom_left = i * (profile_count + 1) + j;
+            let top_left = i * (profile_count + 1) + j + 1;
+            let bottom_right = (i + 1) * (profile_count + 1) + j;
+            let top_right = (i + 1) * (profile_count + 1) + j + 1;
+            triangles.push([top_left, bottom_left, top_right]);
+            triangles.push([top_right, bottom_left, bottom_right]);
+        }
+    }
+
+    let normals = accumulate_normals(&positions, &triangles);
+
+    Surface {
+        start: Vec2::zero(),
+        end: Vec2::new(ring_count as f32, profile_count as f32),
+        count: [ring_count, profile_count],
+        positions,
+        normals,
+        params,
         triangles
     }
 }
@@ -124,6 +260,112 @@ where
     }
 }
 
+fn frac(x: f32) -> f32 {
+    x - x.floor()
+}
+
+// Standard HSV->RGB conversion, h in [0, 1), s and v in [0, 1].
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [f32; 3] {
+    let h6 = h * 6.0;
+    let c = v * s;
+    let x = c * (1.0 - f32::abs(h6 % 2.0 - 1.0));
+    let m = v - c;
+    let (r, g, b) = match h6 as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    [r + m, g + m, b + m]
+}
+
+fn compute_colors<F>(surface: &Surface, f: F) -> Vec<[f32; 4]>
+where
+    F: Fn(Vec2) -> Complex<f32>
+{
+    let mut colors = Vec::new();
+
+    let start = surface.start;
+    let end = surface.end;
+    let count = surface.count;
+    let diff = end - start;
+    let delta_x = diff[0] / count[0] as f32;
+    let delta_y = diff[1] / count[1] as f32;
+
+    for i in 0..=count[0] {
+        for j in 0..=count[1] {
+            let x = start[0] + i as f32 * delta_x;
+            let y = start[1] + j as f32 * delta_y;
+            let w = f(Vec2::new(x, y));
+            let hue = (w.arg() + std::f32::consts::PI) / TAU;
+            let lightness = 0.5 + 0.1 * frac(w.norm().log2());
+            let [r, g, b] = hsv_to_rgb(hue, 1.0, lightness);
+            colors.push([r, g, b, 1.0]);
+        }
+    }
+    colors
+}
+
+// Domain coloring: color each vertex from w = f(z) directly (hue from arg(w),
+// lightness banded by log2(|w|)) instead of baking phase into a periodic UV
+// texture, so zeros and poles stay legible regardless of wrapping.
+pub fn surface_to_domain_colored<F>(surface: &Surface, f: F) -> Mesh
+where
+    F: Fn(Vec2) -> Complex<f32>
+{
+    let positions = surface.positions.iter().map(|p| (*p).into()).collect();
+    let normals = surface.normals.iter().map(|n| (*n).into()).collect();
+    let mut indices = Vec::new();
+    let colors = compute_colors(&surface, f);
+    for t in &surface.triangles {
+        indices.extend_from_slice(t);
+    }
+    Mesh {
+        primitive_topology: PrimitiveTopology::TriangleList,
+        attributes: vec![
+            VertexAttribute::position(positions),
+            VertexAttribute::normal(normals),
+            VertexAttribute::color(colors)
+        ],
+        indices: Some(indices)
+    }
+}
+
+// Tags an entity with which mesh-building path produced its `Handle<Mesh>`
+// (`surface_to_solid`'s uv attribute vs `surface_to_domain_colored`'s color
+// attribute), so `mesh_system` knows which function to re-run when `Mero`
+// changes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MeshColoring {
+    Textured,
+    DomainColored
+}
+
+// Recompute and overwrite the uv VertexAttribute of an existing mesh built
+// by `surface_to_solid`/`surface_to_wireframe`/`surface_to_point_cloud` in
+// place, leaving the position/normal/index buffers (and their GPU uploads)
+// untouched. Much cheaper than rebuilding the whole mesh when only `f`
+// (e.g. the `Mero`) changed.
+pub fn update_uvs<F>(mesh: &mut Mesh, surface: &Surface, f: F)
+where
+    F: Fn(Vec2) -> Vec2
+{
+    let uvs = compute_uvs(surface, f);
+    mesh.attributes[2] = VertexAttribute::uv(uvs);
+}
+
+// Same as `update_uvs` but for meshes built by `surface_to_domain_colored`,
+// where slot 2 holds per-vertex color instead of uv.
+pub fn update_colors<F>(mesh: &mut Mesh, surface: &Surface, f: F)
+where
+    F: Fn(Vec2) -> Complex<f32>
+{
+    let colors = compute_colors(surface, f);
+    mesh.attributes[2] = VertexAttribute::color(colors);
+}
+
 pub fn surface_to_wireframe<F>(surface: &Surface, f: F) -> Mesh
 where
     F: Fn(Vec2) -> Vec2
@@ -200,8 +442,8 @@ pub fn from_complex(z: Complex<f32>) -> Vec2 {
 pub fn to_uv(z: Complex<f32>) -> Vec2 {
     let r = z.norm();
     let phi = z.arg();
-    let frac = phi / (std::f32::consts::PI * 2.0);
-    Vec2::new(0.0, r)
+    let frac = phi / TAU;
+    Vec2::new(frac, r)
 }
 
 pub fn identity(z: Vec2) -> Vec2 {
@@ -224,7 +466,7 @@ impl Mero {
     }
 }
 
-pub fn poly(ps: &Mero, z: Vec2) -> Vec2 {
+pub fn eval_mero(ps: &Mero, z: Vec2) -> Complex<f32> {
     let z = to_complex(z);
     let one = Complex::new(1.0, 0.0);
     let mut val = ps.factor;
@@ -234,5 +476,9 @@ pub fn poly(ps: &Mero, z: Vec2) -> Vec2 {
     for p in ps.poles.iter() {
         val *= one/(z - p)
     }
-    to_uv(val)
+    val
+}
+
+pub fn poly(ps: &Mero, z: Vec2) -> Vec2 {
+    to_uv(eval_mero(ps, z))
 }
@@ -0,0 +1,34 @@
+use bevy::math::*;
+use noise::{NoiseFn, OpenSimplex, Seedable};
+
+// Fractal Brownian motion: sums `octaves` layers of OpenSimplex noise, each
+// one `lacunarity` times higher frequency and `gain` times lower amplitude
+// than the last, normalized so the result stays roughly in [-1, 1].
+pub fn fbm(noise: &OpenSimplex, z: Vec2, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+    let mut freq = 1.0;
+    let mut amp = 1.0;
+    let mut sum = 0.0;
+    let mut max = 0.0;
+
+    for _ in 0..octaves {
+        let sample = noise.get([(z[0] * freq) as f64, (z[1] * freq) as f64]) as f32;
+        sum += sample * amp;
+        max += amp;
+        freq *= lacunarity;
+        amp *= gain;
+    }
+
+    sum / max
+}
+
+// A heightfield generator for `parametric_surface`: layered noise at three
+// octaves (base frequency, then 2.5x frequency at half the amplitude, then
+// 6.25x frequency at a quarter), normalized by `fbm` and scaled by 20, so the
+// base octave's amplitude is 20 / 1.75 (about 11.4), giving terrain/planet-like
+// bumps with large landmasses and finer surface detail. The noise table is
+// built once here and captured by the closure, since `parametric_surface`
+// samples it several times per vertex (finite-difference normals).
+pub fn terrain(seed: u32) -> impl Fn(Vec2) -> Vec3 {
+    let noise = OpenSimplex::new().set_seed(seed);
+    move |z: Vec2| z.extend(20.0 * fbm(&noise, z, 3, 2.5, 0.5))
+}